@@ -5,20 +5,33 @@ use log4rs::{
     config::{Appender, Config, Root},
     encode::pattern::PatternEncoder,
 };
+use sha2::{Digest, Sha256};
 use std::error::Error;
-use std::fs;
-use std::path::Path;
+use std::fmt;
+use std::fs::{self, File, OpenOptions};
+use std::path::{Path, PathBuf};
 use std::{
     io,
     process::{Command, Stdio},
 };
+use zip::ZipArchive;
+use zip::result::ZipError;
+
+mod shell;
+use shell::ShellCommand;
 
 const RIME_SYSTEM_DIR: &str = "/usr/share/rime-data";
+const DEFAULT_LOCAL_ZIP: &str = "./小鹤音形“鼠须管”for macOS.zip";
 
 fn main() -> Result<(), Box<dyn std::error::Error>> {
     // 初始化日志系统
     init_logger()?;
 
+    let args: Vec<String> = std::env::args().collect();
+    if args.get(1).map(String::as_str) == Some("uninstall") {
+        return run_uninstall(args.get(2));
+    }
+
     info!("===== 开始安装小鹤音形输入法 =====");
     info!("时间: {}", Local::now().format("%Y-%m-%d %H:%M:%S"));
 
@@ -40,13 +53,14 @@ fn main() -> Result<(), Box<dyn std::error::Error>> {
         },
     ];
 
-    let dependencies = ["curl", "7z", "rsync"];
+    let dependencies = ["curl", "rsync", "git"];
 
     // 检查并安装依赖
     check_and_install_dependencies(&package_managers, &dependencies)?;
 
     // 1. 获取配置文件
-    let config_dir = match get_config_files(Some("./小鹤音形“鼠须管”for macOS.zip")) {
+    let config_source = parse_config_source(&args)?;
+    let config_dir = match get_config_files(config_source) {
         Ok(dir) => dir,
         Err(e) => {
             error!("获取配置文件失败: {}", e);
@@ -57,7 +71,14 @@ fn main() -> Result<(), Box<dyn std::error::Error>> {
     // 2. 复制文件到系统目录 (需要 sudo)
     info!("\n需要管理员权限来复制文件到系统目录");
     info!("请在提示时输入您的密码");
-    if let Err(e) = copy_to_system_dir(&config_dir, RIME_SYSTEM_DIR) {
+    let backup_policy = BackupPolicy::Numbered { keep: Some(5) };
+    let install_behavior = InstallBehavior::default();
+    if let Err(e) = copy_to_system_dir(
+        &config_dir,
+        RIME_SYSTEM_DIR,
+        &backup_policy,
+        &install_behavior,
+    ) {
         error!("复制配置文件失败: {}", e);
         return Err(e);
     }
@@ -69,6 +90,32 @@ fn main() -> Result<(), Box<dyn std::error::Error>> {
     Ok(())
 }
 
+/// `uninstall` 子命令入口：不带参数恢复最近一次备份（无备份则直接移除），
+/// 带一个编号参数时恢复指定编号的备份
+///
+/// 用法: uninstall [编号]
+///   uninstall        恢复最近一次备份，若没有备份则直接移除系统目录
+///   uninstall <编号>  恢复指定编号的备份（编号可通过本命令的报错提示查看）
+fn run_uninstall(backup_number: Option<&String>) -> Result<(), Box<dyn std::error::Error>> {
+    info!("===== 开始卸载小鹤音形输入法 =====");
+    info!("用法: uninstall [编号]（不带编号则恢复最近一次备份）");
+
+    let install_behavior = InstallBehavior::default();
+
+    match backup_number {
+        Some(number) => {
+            let number: u32 = number.parse().map_err(|_| {
+                format!(
+                    "备份编号必须是数字: {}\n用法: uninstall [编号]",
+                    number
+                )
+            })?;
+            restore_backup_by_number(RIME_SYSTEM_DIR, number, &install_behavior)
+        }
+        None => uninstall(RIME_SYSTEM_DIR, &install_behavior),
+    }
+}
+
 /// 初始化日志系统
 fn init_logger() -> io::Result<()> {
     // 创建日志目录
@@ -151,20 +198,16 @@ fn check_and_install_dependencies(
                     pm.update_cmd, pm.name, pm.install_args, deps
                 );
 
-                info!("将执行以下命令安装依赖:");
-                info!("{}", install_cmd);
                 info!("请在提示时输入您的密码");
 
                 // 执行安装命令
-                let status = Command::new("sh")
+                if let Err(e) = ShellCommand::new("sh")
                     .arg("-c")
                     .arg(&install_cmd)
-                    .stdout(Stdio::inherit())
-                    .stderr(Stdio::inherit())
-                    .status()?;
-
-                if !status.success() {
-                    error!("依赖安装失败: {}", install_cmd);
+                    .interactive()
+                    .run()
+                {
+                    error!("依赖安装失败: {}", e);
                     return Err(format!("依赖安装失败: {}", install_cmd).into());
                 }
 
@@ -180,20 +223,75 @@ fn check_and_install_dependencies(
     Ok(())
 }
 
-/// 查找解压后的配置目录（支持多种策略）
-fn find_config_directory(extract_dir: &str) -> Result<String, Box<dyn std::error::Error>> {
-    // 策略一：查找第一个子目录
-    for entry in fs::read_dir(extract_dir)? {
-        let entry = entry?;
-        let path = entry.path();
-        if path.is_dir() {
-            return Ok(path.to_str().unwrap().to_string());
+/// ZIP 解压过程中可能出现的错误
+#[derive(Debug)]
+enum ExtractError {
+    /// 压缩包本身已损坏或格式不受支持
+    Zip(ZipError),
+    /// 压缩包中的条目被加密，当前不支持无密码解压
+    Encrypted(String),
+    Io(io::Error),
+}
+
+impl fmt::Display for ExtractError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            ExtractError::Zip(e) => write!(f, "压缩包解析失败: {}", e),
+            ExtractError::Encrypted(name) => write!(f, "条目已加密，无法解压: {}", name),
+            ExtractError::Io(e) => write!(f, "IO 错误: {}", e),
+        }
+    }
+}
+
+impl Error for ExtractError {}
+
+impl From<ZipError> for ExtractError {
+    fn from(e: ZipError) -> Self {
+        ExtractError::Zip(e)
+    }
+}
+
+impl From<io::Error> for ExtractError {
+    fn from(e: io::Error) -> Self {
+        ExtractError::Io(e)
+    }
+}
+
+/// 按索引读取一个 ZIP 条目，将"需要密码"的情况映射成专门的加密错误
+fn read_zip_entry(
+    archive: &mut ZipArchive<File>,
+    index: usize,
+) -> Result<zip::read::ZipFile<'_>, ExtractError> {
+    archive.by_index(index).map_err(|e| match &e {
+        ZipError::UnsupportedArchive(msg) if *msg == ZipError::PASSWORD_REQUIRED => {
+            ExtractError::Encrypted(format!("条目 #{}", index))
+        }
+        _ => ExtractError::Zip(e),
+    })
+}
+
+/// 从 ZIP 条目名称中推断配置目录（支持多种策略）
+fn find_config_directory(entry_names: &[String], extract_dir: &str) -> Result<String, Box<dyn std::error::Error>> {
+    // 策略一：查找第一个顶层目录条目
+    for name in entry_names {
+        if name.ends_with('/') && name.matches('/').count() == 1 {
+            let top_level = name.trim_end_matches('/');
+            return Ok(Path::new(extract_dir).join(top_level).to_string_lossy().into_owned());
         }
     }
 
-    // 策略二：如果没有子目录，但有文件，说明是平铺结构，直接使用当前目录
-    if fs::read_dir(extract_dir)?.next().is_some() {
-        info!("ZIP 解压后未找到目录，使用根目录作为配置目录");
+    // 策略二：没有显式目录条目时，取所有文件共同的顶层路径前缀
+    if let Some(first) = entry_names.first() {
+        if let Some((prefix, _)) = first.split_once('/') {
+            if entry_names.iter().all(|n| n.starts_with(&format!("{}/", prefix))) {
+                return Ok(Path::new(extract_dir).join(prefix).to_string_lossy().into_owned());
+            }
+        }
+    }
+
+    // 策略三：平铺结构，直接使用根目录
+    if !entry_names.is_empty() {
+        info!("ZIP 内未找到目录条目，使用根目录作为配置目录");
         return Ok(extract_dir.to_string());
     }
 
@@ -223,53 +321,393 @@ fn get_config_from_local(
         }
     }
 
-    // 执行解压命令
+    // 使用纯 Rust 的 zip 解压，避免依赖外部 7z
     info!("开始解压文件到目录: {}", output_dir);
-    let output = Command::new("7z")
-        .env("LANG", "C.UTF-8") // 使用通用的 C.UTF-8 替代
-        .arg("x") // 解压命令
-        .arg("-y") // 假设所有问题的回答都是 yes
-        .arg(format!("-o{}", output_dir)) // 正确的 -o 参数格式
-        .arg("-bso0") // 关闭标准输出
-        .arg("-bse0") // 关闭错误输出
-        .arg(local_path)
-        .output()?;
+    let file = File::open(local_path).map_err(ExtractError::from)?;
+    let mut archive = ZipArchive::new(file).map_err(ExtractError::from)?;
+
+    let entry_names: Vec<String> = (0..archive.len())
+        .map(|i| read_zip_entry(&mut archive, i).map(|f| f.name().to_string()))
+        .collect::<Result<_, _>>()?;
+
+    for i in 0..archive.len() {
+        let mut entry = read_zip_entry(&mut archive, i)?;
 
-    if !output.status.success() {
-        let stderr = String::from_utf8_lossy(&output.stderr);
-        error!("解压失败，错误信息:\n{}", stderr);
-        return Err("解压配置文件失败".into());
+        let out_path: PathBuf = Path::new(output_dir).join(entry.mangled_name());
+
+        if entry.is_dir() {
+            fs::create_dir_all(&out_path).map_err(ExtractError::from)?;
+            continue;
+        }
+
+        if let Some(parent) = out_path.parent() {
+            fs::create_dir_all(parent).map_err(ExtractError::from)?;
+        }
+
+        let mut out_file = File::create(&out_path).map_err(ExtractError::from)?;
+        io::copy(&mut entry, &mut out_file).map_err(ExtractError::from)?;
+
+        #[cfg(unix)]
+        if let Some(mode) = entry.unix_mode() {
+            use std::os::unix::fs::PermissionsExt;
+            fs::set_permissions(&out_path, fs::Permissions::from_mode(mode))
+                .map_err(ExtractError::from)?;
+        }
     }
 
-    // 查找配置目录
-    let config_dir = find_config_directory(output_dir)?;
+    // 直接根据条目列表定位配置目录，无需再次扫描文件系统
+    let config_dir = find_config_directory(&entry_names, output_dir)?;
 
-    // rename_files_to_utf8(Path::new(&config_dir))?;
+    info!("找到配置目录: {}", config_dir);
+
+    Ok(config_dir)
+}
 
+/// 配置文件的获取来源
+enum ConfigSource<'a> {
+    /// 本地 ZIP 文件路径
+    Local(&'a str),
+    /// 小鹤音形配置的 Git 仓库
+    Git(GitSource),
+    /// 通过 HTTP(S) 下载的 ZIP 压缩包
+    Url {
+        url: &'a str,
+        /// 调用方提供的预期 SHA-256，校验失败则拒绝解压
+        expected_sha256: Option<&'a str>,
+    },
+}
+
+/// Git 仓库来源，`branch` 与 `revision` 互斥
+struct GitSource {
+    url: String,
+    branch: Option<String>,
+    revision: Option<String>,
+}
+
+impl GitSource {
+    /// 构造并校验来源：`branch` 与 `revision` 不能同时指定，
+    /// 两者都未指定时默认跟踪 `master`
+    fn new(
+        url: impl Into<String>,
+        branch: Option<String>,
+        revision: Option<String>,
+    ) -> Result<Self, Box<dyn Error>> {
+        if branch.is_some() && revision.is_some() {
+            return Err("branch 与 revision 不能同时指定".into());
+        }
+
+        let branch = branch.or_else(|| if revision.is_none() { Some("master".to_string()) } else { None });
+
+        Ok(GitSource {
+            url: url.into(),
+            branch,
+            revision,
+        })
+    }
+}
+
+/// 根据命令行参数选择配置来源：
+/// - 不带参数：使用本地 ZIP（`DEFAULT_LOCAL_ZIP`）
+/// - `git <url> [--branch <branch>|--revision <revision>]`：从 Git 仓库获取
+/// - `url <url> [--sha256 <digest>]`：从 HTTP(S) 下载 ZIP
+fn parse_config_source(args: &[String]) -> Result<ConfigSource<'_>, Box<dyn Error>> {
+    match args.get(1).map(String::as_str) {
+        Some("git") => {
+            let url = args
+                .get(2)
+                .ok_or("缺少 Git 仓库地址，用法: git <url> [--branch <分支>|--revision <版本>]")?;
+
+            let mut branch = None;
+            let mut revision = None;
+            let mut i = 3;
+            while i < args.len() {
+                match args[i].as_str() {
+                    "--branch" => {
+                        branch = Some(args.get(i + 1).ok_or("--branch 需要一个值")?.clone());
+                        i += 2;
+                    }
+                    "--revision" => {
+                        revision = Some(args.get(i + 1).ok_or("--revision 需要一个值")?.clone());
+                        i += 2;
+                    }
+                    other => return Err(format!("未知参数: {}", other).into()),
+                }
+            }
+
+            Ok(ConfigSource::Git(GitSource::new(url.clone(), branch, revision)?))
+        }
+        Some("url") => {
+            let url = args
+                .get(2)
+                .ok_or("缺少下载地址，用法: url <url> [--sha256 <摘要>]")?
+                .as_str();
+
+            let expected_sha256 = match args.get(3).map(String::as_str) {
+                Some("--sha256") => {
+                    Some(args.get(4).ok_or("--sha256 需要一个值")?.as_str())
+                }
+                Some(other) => return Err(format!("未知参数: {}", other).into()),
+                None => None,
+            };
+
+            Ok(ConfigSource::Url { url, expected_sha256 })
+        }
+        _ => Ok(ConfigSource::Local(DEFAULT_LOCAL_ZIP)),
+    }
+}
+
+/// 在已有目录结构中查找配置目录（用于 Git 克隆等场景）
+fn find_config_directory_in_fs(dir: &str) -> Result<String, Box<dyn std::error::Error>> {
+    for entry in fs::read_dir(dir)? {
+        let entry = entry?;
+        let path = entry.path();
+        if path.is_dir() && path.file_name().map(|n| n != ".git").unwrap_or(true) {
+            return Ok(path.to_str().unwrap().to_string());
+        }
+    }
+
+    if fs::read_dir(dir)?.next().is_some() {
+        info!("Git 仓库中未找到子目录，使用根目录作为配置目录");
+        return Ok(dir.to_string());
+    }
+
+    error!("Git 仓库中未找到配置文件目录或文件");
+    Err("未找到配置文件目录".into())
+}
+
+/// 从 Git 仓库获取配置并返回配置目录路径
+fn get_config_from_git(
+    source: &GitSource,
+    output_dir: &str,
+) -> Result<String, Box<dyn std::error::Error>> {
+    info!("尝试从 Git 仓库获取配置文件: {}", source.url);
+
+    if Path::new(output_dir).join(".git").exists() {
+        info!("检测到已有克隆，执行 fetch 并重置到目标分支的最新提交或指定版本");
+
+        match &source.revision {
+            Some(revision) => {
+                // 浅克隆的历史里通常不包含任意 commit，需要按指定版本单独 fetch，
+                // 而不是笼统地 fetch --depth 1 origin 再 reset 到它
+                ShellCommand::new("git")
+                    .args(["-C", output_dir, "fetch", "--depth", "1", "origin", revision])
+                    .run()?;
+
+                ShellCommand::new("git")
+                    .args(["-C", output_dir, "reset", "--hard", "FETCH_HEAD"])
+                    .run()?;
+            }
+            None => {
+                let branch = source.branch.as_deref().unwrap_or("master");
+
+                ShellCommand::new("git")
+                    .args(["-C", output_dir, "fetch", "--depth", "1", "origin", branch])
+                    .run()?;
+
+                ShellCommand::new("git")
+                    .args(["-C", output_dir, "reset", "--hard", "FETCH_HEAD"])
+                    .run()?;
+            }
+        }
+    } else {
+        if Path::new(output_dir).exists() {
+            fs::remove_dir_all(output_dir)?;
+        }
+
+        if let Some(revision) = &source.revision {
+            ShellCommand::new("git")
+                .args(["clone", &source.url, output_dir])
+                .interactive()
+                .run()?;
+
+            ShellCommand::new("git")
+                .args(["-C", output_dir, "checkout", revision])
+                .run()?;
+        } else {
+            let branch = source.branch.as_deref().unwrap_or("master");
+            ShellCommand::new("git")
+                .args([
+                    "clone", "--depth", "1", "--branch", branch, &source.url, output_dir,
+                ])
+                .interactive()
+                .run()?;
+        }
+    }
+
+    let config_dir = find_config_directory_in_fs(output_dir)?;
     info!("找到配置目录: {}", config_dir);
 
     Ok(config_dir)
 }
 
-/// 获取配置文件：使用本地路径
-fn get_config_files(local_path: Option<&str>) -> Result<String, Box<dyn Error>> {
-    info!("获取小鹤音形配置文件……");
+const DOWNLOAD_MAX_ATTEMPTS: u32 = 3;
 
-    // 尝试从本地获取
-    if let Some(path) = local_path {
-        match get_config_from_local(path, "./extracted") {
-            Ok(config_dir) => return Ok(config_dir),
-            Err(err) => error!("从本地获取配置文件失败: {}", err),
+/// 将远程 ZIP 下载到本地，支持断点续传、失败重试及 SHA-256 校验
+fn download_config_archive(
+    url: &str,
+    expected_sha256: Option<&str>,
+) -> Result<String, Box<dyn Error>> {
+    let parsed = reqwest::Url::parse(url).map_err(|e| format!("非法的下载地址: {}", e))?;
+    if parsed.scheme() != "http" && parsed.scheme() != "https" {
+        return Err(format!("不支持的下载协议: {}", parsed.scheme()).into());
+    }
+
+    let download_dir = "./downloads";
+    fs::create_dir_all(download_dir)?;
+
+    let file_name = parsed
+        .path_segments()
+        .and_then(|mut segments| segments.next_back())
+        .filter(|s| !s.is_empty())
+        .unwrap_or("config.zip");
+    let dest_path = Path::new(download_dir).join(file_name);
+
+    let client = reqwest::blocking::Client::new();
+
+    let mut last_err: Option<Box<dyn Error>> = None;
+    for attempt in 1..=DOWNLOAD_MAX_ATTEMPTS {
+        info!("开始下载配置文件 (第 {} 次尝试): {}", attempt, url);
+        match download_with_resume(&client, &parsed, &dest_path) {
+            Ok(()) => {
+                last_err = None;
+                break;
+            }
+            Err(e) => {
+                warn!("下载失败（第 {}/{} 次尝试）: {}", attempt, DOWNLOAD_MAX_ATTEMPTS, e);
+                last_err = Some(e);
+            }
         }
     }
 
-    Err("无法获取配置文件，请检查本地路径".into())
+    if let Some(e) = last_err {
+        return Err(format!("下载配置文件失败，已尝试 {} 次: {}", DOWNLOAD_MAX_ATTEMPTS, e).into());
+    }
+
+    if let Some(expected) = expected_sha256 {
+        verify_sha256(&dest_path, expected)?;
+    }
+
+    Ok(dest_path.to_string_lossy().into_owned())
+}
+
+/// 通过 HEAD 请求获取远端文件大小，用于判断本地已下载内容是否仍然有效
+fn remote_content_length(client: &reqwest::blocking::Client, url: &reqwest::Url) -> Option<u64> {
+    client
+        .head(url.clone())
+        .send()
+        .ok()
+        .filter(|resp| resp.status().is_success())
+        .and_then(|resp| resp.content_length())
+}
+
+/// 下载一次，若目标文件已部分存在则通过 Range 请求续传
+fn download_with_resume(
+    client: &reqwest::blocking::Client,
+    url: &reqwest::Url,
+    dest: &Path,
+) -> Result<(), Box<dyn Error>> {
+    let existing_len = fs::metadata(dest).map(|m| m.len()).unwrap_or(0);
+    let remote_len = remote_content_length(client, url);
+
+    // 只有确认远端大小、且本地内容小于远端大小时，才续传；本地大小与远端一致时视为已完整并跳过下载；
+    // 否则（本地内容超出远端大小、或远端大小未知）删除本地文件后重新下载，避免把旧内容和新内容拼接在一起
+    let resume_from = match remote_len {
+        Some(remote_len) if existing_len > 0 && existing_len == remote_len => {
+            info!("本地文件已完整（{} 字节），跳过下载: {}", existing_len, dest.display());
+            return Ok(());
+        }
+        Some(remote_len) if existing_len > 0 && existing_len < remote_len => existing_len,
+        _ => {
+            if existing_len > 0 {
+                fs::remove_file(dest)?;
+            }
+            0
+        }
+    };
+
+    let mut request = client.get(url.clone());
+    if resume_from > 0 {
+        request = request.header(reqwest::header::RANGE, format!("bytes={}-", resume_from));
+    }
+
+    let response = request.send()?;
+
+    // 服务器认为续传的起始位置已经越界，说明本地文件其实已经是完整的
+    if response.status() == reqwest::StatusCode::RANGE_NOT_SATISFIABLE {
+        info!("服务器返回 416，视为本地文件已完整: {}", dest.display());
+        return Ok(());
+    }
+
+    let mut response = response.error_for_status()?;
+
+    let mut file = if resume_from > 0 && response.status() == reqwest::StatusCode::PARTIAL_CONTENT {
+        OpenOptions::new().append(true).open(dest)?
+    } else {
+        File::create(dest)?
+    };
+
+    io::copy(&mut response, &mut file)?;
+    Ok(())
+}
+
+/// 校验文件的 SHA-256，不匹配时直接报错，避免损坏或被篡改的压缩包流入系统目录
+fn verify_sha256(path: &Path, expected: &str) -> Result<(), Box<dyn Error>> {
+    info!("校验下载文件的 SHA-256: {}", path.display());
+
+    let mut file = File::open(path)?;
+    let mut hasher = Sha256::new();
+    io::copy(&mut file, &mut hasher)?;
+    let actual = format!("{:x}", hasher.finalize());
+
+    if !actual.eq_ignore_ascii_case(expected) {
+        error!("SHA-256 校验失败: 期望 {}, 实际 {}", expected, actual);
+        return Err(format!("SHA-256 校验失败: 期望 {}, 实际 {}", expected, actual).into());
+    }
+
+    info!("SHA-256 校验通过: {}", actual);
+    Ok(())
+}
+
+/// 获取配置文件：支持本地 ZIP、Git 仓库与 HTTP(S) 下载三种来源
+fn get_config_files(source: ConfigSource) -> Result<String, Box<dyn Error>> {
+    info!("获取小鹤音形配置文件……");
+
+    match source {
+        ConfigSource::Local(path) => match get_config_from_local(path, "./extracted") {
+            Ok(config_dir) => Ok(config_dir),
+            Err(err) => {
+                error!("从本地获取配置文件失败: {}", err);
+                Err("无法获取配置文件，请检查本地路径".into())
+            }
+        },
+        ConfigSource::Git(git_source) => {
+            match get_config_from_git(&git_source, "./extracted-git") {
+                Ok(config_dir) => Ok(config_dir),
+                Err(err) => {
+                    error!("从 Git 仓库获取配置文件失败: {}", err);
+                    Err("无法获取配置文件，请检查 Git 仓库地址".into())
+                }
+            }
+        }
+        ConfigSource::Url { url, expected_sha256 } => {
+            let archive_path = download_config_archive(url, expected_sha256)?;
+            match get_config_from_local(&archive_path, "./extracted") {
+                Ok(config_dir) => Ok(config_dir),
+                Err(err) => {
+                    error!("解压下载的配置文件失败: {}", err);
+                    Err("无法获取配置文件，请检查下载地址".into())
+                }
+            }
+        }
+    }
 }
 
 /// 复制配置文件到系统目录 (需要sudo权限)
 fn copy_to_system_dir(
     config_dir: &str,
     rime_system_dir: &str,
+    backup_policy: &BackupPolicy,
+    install_behavior: &InstallBehavior,
 ) -> Result<(), Box<dyn std::error::Error>> {
     info!("复制配置文件到系统目录: {}", rime_system_dir);
 
@@ -279,23 +717,23 @@ fn copy_to_system_dir(
     }
 
     let target_path = Path::new(rime_system_dir);
-    let backup_dir = format!(
-        "/usr/share/rime-backup-{}",
-        Local::now().format("%Y%m%d_%H%M%S")
-    );
 
     // 备份现有配置（如果存在）
     if target_path.exists() {
         info!("目标目录 {} 已存在", rime_system_dir);
 
-        // 创建备份目录
-        create_dir_with_sudo(&backup_dir)?;
-
         // 检查是否非空
         let is_empty = fs::read_dir(target_path)?.next().is_none();
         if !is_empty {
-            info!("开始备份现有配置到 {}", backup_dir);
-            run_rsync_with_sudo(target_path.to_str().unwrap(), &backup_dir)?;
+            match backup_target_path(rime_system_dir, backup_policy)? {
+                Some(backup_dir) => {
+                    let backup_dir = backup_dir.to_string_lossy().into_owned();
+                    info!("开始备份现有配置到 {}", backup_dir);
+                    create_dir_with_sudo(&backup_dir)?;
+                    run_rsync_with_sudo(rime_system_dir, &backup_dir)?;
+                }
+                None => info!("备份策略为 None，跳过备份"),
+            }
         } else {
             info!("目标目录为空，跳过备份");
         }
@@ -311,110 +749,377 @@ fn copy_to_system_dir(
     run_rsync_with_sudo(config_dir, rime_system_dir)?;
 
     // 设置正确权限
-    fix_permissions(rime_system_dir)?;
+    fix_permissions(rime_system_dir, install_behavior)?;
 
     info!("✅ 配置文件已成功复制到系统目录");
 
     Ok(())
 }
 
+/// 备份策略，语义对齐 coreutils `install --backup` 的几种取值
+enum BackupPolicy {
+    /// 不备份，直接覆盖
+    None,
+    /// 单个备份，使用固定后缀（如 `~`），覆盖上一次的备份
+    Simple { suffix: String },
+    /// 若备份目录中已存在编号备份则沿用编号规则，否则退化为 Simple
+    Existing { suffix: String, keep: Option<u32> },
+    /// 总是使用编号备份 `name.~N~`
+    Numbered { keep: Option<u32> },
+}
+
+/// 扫描备份所在目录，返回已存在的编号备份（`base_name.~N~`）的编号列表
+fn numbered_backup_candidates(parent: &Path, base_name: &str) -> Result<Vec<u32>, Box<dyn Error>> {
+    let mut found = Vec::new();
+    if !parent.exists() {
+        return Ok(found);
+    }
+
+    let prefix = format!("{}.~", base_name);
+    for entry in fs::read_dir(parent)? {
+        let name = entry?.file_name();
+        let name = name.to_string_lossy();
+        if let Some(rest) = name.strip_prefix(&prefix) {
+            if let Some(num_str) = rest.strip_suffix('~') {
+                if let Ok(n) = num_str.parse::<u32>() {
+                    found.push(n);
+                }
+            }
+        }
+    }
+
+    Ok(found)
+}
+
+/// 按策略裁剪掉最旧的编号备份，只保留最新的 `keep` 份
+fn prune_numbered_backups(
+    parent: &Path,
+    base_name: &str,
+    existing: &[u32],
+    keep: u32,
+) -> Result<(), Box<dyn Error>> {
+    if (existing.len() as u32) < keep {
+        return Ok(());
+    }
+
+    let mut sorted = existing.to_vec();
+    sorted.sort_unstable();
+    let remove_count = sorted.len().saturating_sub(keep as usize);
+
+    for n in sorted.into_iter().take(remove_count) {
+        let path = parent.join(format!("{}.~{}~", base_name, n));
+        info!("裁剪旧备份: {}", path.display());
+        remove_dir_with_sudo(&path.to_string_lossy())?;
+    }
+
+    Ok(())
+}
+
+/// 根据备份策略计算本次应使用的备份路径（`None` 表示不备份）
+fn backup_target_path(
+    rime_system_dir: &str,
+    policy: &BackupPolicy,
+) -> Result<Option<PathBuf>, Box<dyn Error>> {
+    let target = Path::new(rime_system_dir);
+    let parent = target.parent().unwrap_or_else(|| Path::new("/"));
+    let base_name = target
+        .file_name()
+        .and_then(|n| n.to_str())
+        .ok_or("无法解析系统目录名称")?;
+
+    match policy {
+        BackupPolicy::None => Ok(None),
+        BackupPolicy::Simple { suffix } => {
+            Ok(Some(parent.join(format!("{}{}", base_name, suffix))))
+        }
+        BackupPolicy::Existing { suffix, keep } => {
+            let existing = numbered_backup_candidates(parent, base_name)?;
+            if existing.is_empty() {
+                Ok(Some(parent.join(format!("{}{}", base_name, suffix))))
+            } else {
+                // 这次操作即将再添加一份备份，因此裁剪到 keep - 1，使最终总数等于 keep
+                if let Some(keep) = keep {
+                    prune_numbered_backups(parent, base_name, &existing, keep.saturating_sub(1))?;
+                }
+                let next = existing.iter().max().copied().unwrap_or(0) + 1;
+                Ok(Some(parent.join(format!("{}.~{}~", base_name, next))))
+            }
+        }
+        BackupPolicy::Numbered { keep } => {
+            let existing = numbered_backup_candidates(parent, base_name)?;
+            // 这次操作即将再添加一份备份，因此裁剪到 keep - 1，使最终总数等于 keep
+            if let Some(keep) = keep {
+                prune_numbered_backups(parent, base_name, &existing, keep.saturating_sub(1))?;
+            }
+            let next = existing.iter().max().copied().unwrap_or(0) + 1;
+            Ok(Some(parent.join(format!("{}.~{}~", base_name, next))))
+        }
+    }
+}
+
+/// 一份可供回滚的备份
+struct BackupEntry {
+    path: PathBuf,
+    /// 编号备份（`name.~N~`）的序号；单一的 Simple 备份（`name~`）为 `None`
+    number: Option<u32>,
+}
+
+/// 列出系统目录旁边所有可用的备份，按新旧排序（末尾为最新）
+fn list_backups(rime_system_dir: &str) -> Result<Vec<BackupEntry>, Box<dyn Error>> {
+    let target = Path::new(rime_system_dir);
+    let parent = target.parent().unwrap_or_else(|| Path::new("/"));
+    let base_name = target
+        .file_name()
+        .and_then(|n| n.to_str())
+        .ok_or("无法解析系统目录名称")?;
+
+    let mut backups = Vec::new();
+    if parent.exists() {
+        let numbered_prefix = format!("{}.~", base_name);
+        let simple_name = format!("{}~", base_name);
+
+        for entry in fs::read_dir(parent)? {
+            let entry = entry?;
+            let name = entry.file_name();
+            let name = name.to_string_lossy();
+
+            if let Some(rest) = name.strip_prefix(&numbered_prefix) {
+                if let Some(num_str) = rest.strip_suffix('~') {
+                    if let Ok(n) = num_str.parse::<u32>() {
+                        backups.push(BackupEntry {
+                            path: entry.path(),
+                            number: Some(n),
+                        });
+                        continue;
+                    }
+                }
+            }
+
+            if name == simple_name {
+                backups.push(BackupEntry {
+                    path: entry.path(),
+                    number: None,
+                });
+            }
+        }
+    }
+
+    backups.sort_by_key(|b| b.number.unwrap_or(0));
+    Ok(backups)
+}
+
+/// 将指定备份恢复到系统目录，沿用安装时相同的 rsync `--delete` 语义与权限修复
+fn restore_backup(
+    backup: &Path,
+    rime_system_dir: &str,
+    install_behavior: &InstallBehavior,
+) -> Result<(), Box<dyn std::error::Error>> {
+    info!("从备份恢复: {} -> {}", backup.display(), rime_system_dir);
+
+    create_dir_with_sudo(rime_system_dir)?;
+    run_rsync_with_sudo(
+        backup.to_str().ok_or("备份路径包含非法字符")?,
+        rime_system_dir,
+    )?;
+    fix_permissions(rime_system_dir, install_behavior)?;
+
+    info!("✅ 已恢复备份: {}", backup.display());
+    Ok(())
+}
+
+/// 恢复最近一次备份（编号最大的备份，或唯一的 Simple 备份）
+fn restore_latest_backup(
+    rime_system_dir: &str,
+    install_behavior: &InstallBehavior,
+) -> Result<(), Box<dyn std::error::Error>> {
+    let backups = list_backups(rime_system_dir)?;
+    let latest = backups.last().ok_or("未找到可用的备份")?;
+    restore_backup(&latest.path, rime_system_dir, install_behavior)
+}
+
+/// 恢复指定编号的备份；若编号不存在，报错信息中列出当前可用的编号，方便用户重试
+fn restore_backup_by_number(
+    rime_system_dir: &str,
+    number: u32,
+    install_behavior: &InstallBehavior,
+) -> Result<(), Box<dyn std::error::Error>> {
+    let backups = list_backups(rime_system_dir)?;
+    let target = backups
+        .iter()
+        .find(|b| b.number == Some(number))
+        .map(|b| b.path.clone())
+        .ok_or_else(|| {
+            let available: Vec<String> = backups
+                .iter()
+                .filter_map(|b| b.number)
+                .map(|n| n.to_string())
+                .collect();
+            if available.is_empty() {
+                format!("未找到编号为 {} 的备份，当前没有任何编号备份", number)
+            } else {
+                format!(
+                    "未找到编号为 {} 的备份，当前可用编号: {}",
+                    number,
+                    available.join(", ")
+                )
+            }
+        })?;
+    restore_backup(&target, rime_system_dir, install_behavior)
+}
+
+/// 卸载小鹤音形：恢复最近一次备份；若没有可用备份，则直接移除系统目录
+fn uninstall(
+    rime_system_dir: &str,
+    install_behavior: &InstallBehavior,
+) -> Result<(), Box<dyn std::error::Error>> {
+    info!("开始卸载小鹤音形输入法: {}", rime_system_dir);
+
+    if !Path::new(rime_system_dir).exists() {
+        return Err(format!("系统目录不存在，无需卸载: {}", rime_system_dir).into());
+    }
+
+    if !list_backups(rime_system_dir)?.is_empty() {
+        restore_latest_backup(rime_system_dir, install_behavior)?;
+        info!("✅ 卸载完成，已恢复到上一次备份");
+        return Ok(());
+    }
+
+    warn!("未找到可恢复的备份，直接移除系统目录");
+    ShellCommand::new("rm")
+        .sudo()
+        .args(["-rf", rime_system_dir])
+        .run()?;
+    info!("✅ 卸载完成，系统目录已移除");
+    Ok(())
+}
+
 // 封装创建目录逻辑
 fn create_dir_with_sudo(dir: &str) -> Result<(), Box<dyn std::error::Error>> {
-    let status = Command::new("sudo")
-        .arg("mkdir")
-        .arg("-p")
-        .arg(dir)
-        .status()?;
-    if !status.success() {
-        return Err(format!("创建目录失败: {}", dir).into());
-    }
+    ShellCommand::new("mkdir").sudo().args(["-p", dir]).run()?;
+    Ok(())
+}
+
+// 封装删除目录逻辑（用于裁剪旧备份）
+fn remove_dir_with_sudo(dir: &str) -> Result<(), Box<dyn std::error::Error>> {
+    ShellCommand::new("rm").sudo().args(["-rf", dir]).run()?;
     Ok(())
 }
 
 fn run_rsync_with_sudo(src: &str, dest: &str) -> Result<(), Box<dyn std::error::Error>> {
     info!("复制文件从 {} 到 {}", src, dest);
 
-    // 设置正确的语言环境
-    let mut cmd = Command::new("sudo");
-    cmd.env("LANG", "zh_CN.UTF-8")
+    ShellCommand::new("rsync")
+        .sudo()
+        .env("LANG", "zh_CN.UTF-8")
         .env("LC_ALL", "zh_CN.UTF-8")
-        .arg("rsync")
         .arg("-a") // 存档模式，保留所有属性
         .arg("--iconv=UTF-8,UTF-8") // 确保编码转换正确
         .arg("--delete") // 删除目标中多余文件，保持一致性
         .arg(format!("{}/", src)) // 结尾斜杠表示复制内容而非目录本身
-        .arg(format!("{}/", dest));
-
-    let output = cmd.output()?;
-
-    if !output.status.success() {
-        error!(
-            "rsync 失败 stdout: {:?}",
-            String::from_utf8_lossy(&output.stdout)
-        );
-        error!(
-            "rsync 错误 stderr: {:?}",
-            String::from_utf8_lossy(&output.stderr)
-        );
-        return Err("rsync 失败".into());
-    }
+        .arg(format!("{}/", dest))
+        .run()?;
 
     Ok(())
 }
 
 /// 设置文件和目录权限
-fn fix_permissions(rime_system_dir: &str) -> Result<(), Box<dyn std::error::Error>> {
+/// 安装时的权限与属主行为，所有字段留空时保持原有默认行为
+#[derive(Default)]
+struct InstallBehavior {
+    /// 统一应用于整个目录树的权限（八进制），不指定则保持目录 755 / 文件 644 / *.bin 755 的默认分级
+    specified_mode: Option<u32>,
+    owner: Option<String>,
+    group: Option<String>,
+}
+
+/// 解析 /etc/passwd，按用户名查找 uid
+fn resolve_uid(name: &str) -> Result<u32, Box<dyn Error>> {
+    let passwd = fs::read_to_string("/etc/passwd")?;
+    passwd
+        .lines()
+        .filter_map(|line| {
+            let fields: Vec<&str> = line.split(':').collect();
+            if fields.first() == Some(&name) {
+                fields.get(2)?.parse::<u32>().ok()
+            } else {
+                None
+            }
+        })
+        .next()
+        .ok_or_else(|| format!("未找到用户: {}", name).into())
+}
+
+/// 解析 /etc/group，按组名查找 gid
+fn resolve_gid(name: &str) -> Result<u32, Box<dyn Error>> {
+    let group = fs::read_to_string("/etc/group")?;
+    group
+        .lines()
+        .filter_map(|line| {
+            let fields: Vec<&str> = line.split(':').collect();
+            if fields.first() == Some(&name) {
+                fields.get(2)?.parse::<u32>().ok()
+            } else {
+                None
+            }
+        })
+        .next()
+        .ok_or_else(|| format!("未找到用户组: {}", name).into())
+}
+
+/// 设置文件和目录权限，并在指定了 owner/group 时一并设置属主
+fn fix_permissions(
+    rime_system_dir: &str,
+    behavior: &InstallBehavior,
+) -> Result<(), Box<dyn std::error::Error>> {
     info!("修复系统目录权限: {}", rime_system_dir);
 
-    // 使用sudo命令设置目录权限
-    let status = Command::new("sudo")
-        .arg("find")
-        .arg(rime_system_dir)
-        .arg("-type")
-        .arg("d")
-        .arg("-exec")
-        .arg("chmod")
-        .arg("755")
-        .arg("{}")
-        .arg(";")
-        .status()?;
-
-    if !status.success() {
-        return Err("设置目录权限失败".into());
-    }
-
-    // 使用sudo命令设置文件权限
-    let status = Command::new("sudo")
-        .arg("find")
-        .arg(rime_system_dir)
-        .arg("-type")
-        .arg("f")
-        .arg("-exec")
-        .arg("chmod")
-        .arg("644")
-        .arg("{}")
-        .arg(";")
-        .status()?;
-
-    if !status.success() {
-        return Err("设置文件权限失败".into());
-    }
-
-    // 特殊处理.bin文件
-    let status = Command::new("sudo")
-        .arg("find")
-        .arg(rime_system_dir)
-        .arg("-name")
-        .arg("*.bin")
-        .arg("-exec")
-        .arg("chmod")
-        .arg("755")
-        .arg("{}")
-        .arg(";")
-        .status()?;
-
-    if !status.success() {
-        warn!("未能设置.bin文件的执行权限");
+    match behavior.specified_mode {
+        // 指定了统一权限：单次递归 chmod，而不是分别处理目录/文件/*.bin
+        Some(mode) => {
+            info!("应用指定权限 {:o}（递归）: {}", mode, rime_system_dir);
+            ShellCommand::new("chmod")
+                .sudo()
+                .args(["-R", &format!("{:o}", mode), rime_system_dir])
+                .run()?;
+        }
+        // 未指定：保持原有默认行为
+        None => {
+            // 设置目录权限
+            ShellCommand::new("find")
+                .sudo()
+                .args([rime_system_dir, "-type", "d", "-exec", "chmod", "755", "{}", ";"])
+                .run()?;
+
+            // 设置文件权限
+            ShellCommand::new("find")
+                .sudo()
+                .args([rime_system_dir, "-type", "f", "-exec", "chmod", "644", "{}", ";"])
+                .run()?;
+
+            // 特殊处理.bin文件
+            if let Err(e) = ShellCommand::new("find")
+                .sudo()
+                .args([rime_system_dir, "-name", "*.bin", "-exec", "chmod", "755", "{}", ";"])
+                .run()
+            {
+                warn!("未能设置.bin文件的执行权限: {}", e);
+            }
+        }
+    }
+
+    if behavior.owner.is_some() || behavior.group.is_some() {
+        let owner_spec = match (&behavior.owner, &behavior.group) {
+            (Some(owner), Some(group)) => format!("{}:{}", resolve_uid(owner)?, resolve_gid(group)?),
+            (Some(owner), None) => resolve_uid(owner)?.to_string(),
+            (None, Some(group)) => format!(":{}", resolve_gid(group)?),
+            (None, None) => unreachable!(),
+        };
+
+        info!("设置属主 {}（递归）: {}", owner_spec, rime_system_dir);
+        ShellCommand::new("chown")
+            .sudo()
+            .args(["-R", &owner_spec, rime_system_dir])
+            .run()?;
     }
 
     info!("权限修复完成");
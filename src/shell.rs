@@ -0,0 +1,159 @@
+use log::info;
+use std::error::Error;
+use std::fmt;
+use std::process::{Command, Stdio};
+
+/// 命令执行失败时的详细信息：退出码 + 捕获到的标准输出/错误
+#[derive(Debug)]
+pub struct ShellError {
+    pub program: String,
+    pub args: Vec<String>,
+    pub exit_code: Option<i32>,
+    pub stdout: String,
+    pub stderr: String,
+}
+
+impl fmt::Display for ShellError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "命令执行失败: {} {} (退出码: {:?})\nstdout: {}\nstderr: {}",
+            self.program,
+            self.args.join(" "),
+            self.exit_code,
+            self.stdout.trim(),
+            self.stderr.trim()
+        )
+    }
+}
+
+impl Error for ShellError {}
+
+/// 统一封装 `std::process::Command`：支持 `.sudo()` 提权、`.env()`，
+/// 执行前通过 `info!` 打印完整命令行，失败时返回带退出码与输出的 [`ShellError`]
+pub struct ShellCommand {
+    program: String,
+    args: Vec<String>,
+    envs: Vec<(String, String)>,
+    sudo: bool,
+    interactive: bool,
+}
+
+impl ShellCommand {
+    pub fn new(program: impl Into<String>) -> Self {
+        ShellCommand {
+            program: program.into(),
+            args: Vec::new(),
+            envs: Vec::new(),
+            sudo: false,
+            interactive: false,
+        }
+    }
+
+    /// 通过 sudo 提权执行；密码提示继承自当前终端
+    pub fn sudo(mut self) -> Self {
+        self.sudo = true;
+        self
+    }
+
+    /// 继承父进程的 stdin/stdout/stderr，而不是捕获输出。
+    /// 用于耗时较长、需要展示实时进度或读取交互式输入（如密码提示）的命令。
+    pub fn interactive(mut self) -> Self {
+        self.interactive = true;
+        self
+    }
+
+    pub fn arg(mut self, arg: impl Into<String>) -> Self {
+        self.args.push(arg.into());
+        self
+    }
+
+    pub fn args<I, S>(mut self, args: I) -> Self
+    where
+        I: IntoIterator<Item = S>,
+        S: Into<String>,
+    {
+        self.args.extend(args.into_iter().map(Into::into));
+        self
+    }
+
+    pub fn env(mut self, key: impl Into<String>, value: impl Into<String>) -> Self {
+        self.envs.push((key.into(), value.into()));
+        self
+    }
+
+    /// 执行命令并返回捕获到的 stdout；失败时返回携带退出码与输出的 [`ShellError`]。
+    /// `interactive` 模式下不捕获输出，成功时返回空字符串
+    pub fn run(self) -> Result<String, ShellError> {
+        let (program, args) = if self.sudo {
+            let mut full_args = vec![self.program.clone()];
+            full_args.extend(self.args.iter().cloned());
+            ("sudo".to_string(), full_args)
+        } else {
+            (self.program.clone(), self.args.clone())
+        };
+
+        info!("执行命令: {} {}", program, args.join(" "));
+
+        let mut cmd = Command::new(&program);
+        cmd.args(&args);
+        for (key, value) in &self.envs {
+            cmd.env(key, value);
+        }
+
+        if self.interactive {
+            // 继承终端：既能实时看到输出，也能回应 sudo 密码提示
+            cmd.stdin(Stdio::inherit())
+                .stdout(Stdio::inherit())
+                .stderr(Stdio::inherit());
+
+            let status = cmd.status().map_err(|e| ShellError {
+                program: program.clone(),
+                args: args.clone(),
+                exit_code: None,
+                stdout: String::new(),
+                stderr: e.to_string(),
+            })?;
+
+            if !status.success() {
+                return Err(ShellError {
+                    program,
+                    args,
+                    exit_code: status.code(),
+                    stdout: String::new(),
+                    stderr: String::new(),
+                });
+            }
+
+            return Ok(String::new());
+        }
+
+        // sudo 的密码提示需要读取终端的标准输入
+        if self.sudo {
+            cmd.stdin(Stdio::inherit());
+        }
+
+        let output = cmd.output().map_err(|e| ShellError {
+            program: program.clone(),
+            args: args.clone(),
+            exit_code: None,
+            stdout: String::new(),
+            stderr: e.to_string(),
+        })?;
+
+        let stdout = String::from_utf8_lossy(&output.stdout).into_owned();
+        let stderr = String::from_utf8_lossy(&output.stderr).into_owned();
+
+        if !output.status.success() {
+            return Err(ShellError {
+                program,
+                args,
+                exit_code: output.status.code(),
+                stdout,
+                stderr,
+            });
+        }
+
+        Ok(stdout)
+    }
+}